@@ -0,0 +1,165 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! TOML config file describing API endpoints, with per-host scheme and
+//! certificate verification settings.
+
+use serde::Deserialize;
+use std::{error::Error, fs, path::Path};
+
+/// URI scheme used to reach an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    /// Plain HTTP.
+    Http,
+    /// TLS-encrypted HTTPS.
+    Https,
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::Http
+    }
+}
+
+impl Scheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Https => "https",
+        }
+    }
+}
+
+/// A single API endpoint to send transactions to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Endpoint {
+    /// Host (and optional port) of the node, e.g. `127.0.0.1:8080`.
+    pub host: String,
+    /// URI scheme to use when connecting to this endpoint.
+    #[serde(default)]
+    pub scheme: Scheme,
+    /// Optional path prefix inserted before the explorer API path, for nodes
+    /// reachable only through a reverse proxy.
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Disables TLS certificate verification for this endpoint; useful for
+    /// self-signed test clusters.
+    #[serde(default)]
+    pub no_cert_verification: bool,
+}
+
+impl Endpoint {
+    /// Creates an endpoint for a bare `--api` host: plain HTTP, no path
+    /// prefix, certificate verification enabled.
+    pub fn from_host(host: String) -> Self {
+        Self {
+            host,
+            scheme: Scheme::default(),
+            path_prefix: None,
+            no_cert_verification: false,
+        }
+    }
+
+    /// Full URL of the transactions explorer endpoint for this host.
+    pub fn transactions_url(&self) -> String {
+        match self.path_prefix.as_deref().map(|p| p.trim_matches('/')) {
+            Some(prefix) if !prefix.is_empty() => format!(
+                "{}://{}/{}/api/explorer/v1/transactions",
+                self.scheme.as_str(),
+                self.host,
+                prefix
+            ),
+            _ => format!(
+                "{}://{}/api/explorer/v1/transactions",
+                self.scheme.as_str(),
+                self.host
+            ),
+        }
+    }
+}
+
+/// Top-level config file format listing API endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Endpoints to merge with any hosts passed via `--api`.
+    #[serde(default)]
+    pub endpoints: Vec<Endpoint>,
+}
+
+impl Config {
+    /// Reads and parses a TOML config file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+#[test]
+fn test_transactions_url_http_no_prefix() {
+    let endpoint = Endpoint::from_host("127.0.0.1:8080".to_owned());
+    assert_eq!(
+        endpoint.transactions_url(),
+        "http://127.0.0.1:8080/api/explorer/v1/transactions"
+    );
+}
+
+#[test]
+fn test_transactions_url_https() {
+    let endpoint = Endpoint {
+        scheme: Scheme::Https,
+        ..Endpoint::from_host("node.example.com".to_owned())
+    };
+    assert_eq!(
+        endpoint.transactions_url(),
+        "https://node.example.com/api/explorer/v1/transactions"
+    );
+}
+
+#[test]
+fn test_transactions_url_with_path_prefix() {
+    let endpoint = Endpoint {
+        path_prefix: Some("proxy".to_owned()),
+        ..Endpoint::from_host("node.example.com".to_owned())
+    };
+    assert_eq!(
+        endpoint.transactions_url(),
+        "http://node.example.com/proxy/api/explorer/v1/transactions"
+    );
+}
+
+#[test]
+fn test_transactions_url_trims_slashes_from_prefix() {
+    let endpoint = Endpoint {
+        path_prefix: Some("/proxy/".to_owned()),
+        ..Endpoint::from_host("node.example.com".to_owned())
+    };
+    assert_eq!(
+        endpoint.transactions_url(),
+        "http://node.example.com/proxy/api/explorer/v1/transactions"
+    );
+}
+
+#[test]
+fn test_transactions_url_empty_prefix_is_ignored() {
+    let endpoint = Endpoint {
+        path_prefix: Some("/".to_owned()),
+        ..Endpoint::from_host("node.example.com".to_owned())
+    };
+    assert_eq!(
+        endpoint.transactions_url(),
+        "http://node.example.com/api/explorer/v1/transactions"
+    );
+}
@@ -0,0 +1,197 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Confirmation subsystem that tracks submitted transactions and polls the
+//! explorer API for their committed/rejected status.
+
+use atomic_counter::{AtomicCounter, RelaxedCounter};
+use exonum::crypto::Hash;
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{atomic::AtomicBool, atomic::Ordering, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How often a checker thread polls the explorer API for pending transactions.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of pending transactions polled concurrently within one
+/// poll round.
+const POLL_CONCURRENCY: usize = 32;
+
+/// Status of a transaction as reported by the explorer API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+enum ExplorerStatus {
+    InPool,
+    Committed,
+    Rejected,
+}
+
+/// Tracks in-flight transactions and polls the explorer API for their final
+/// status.
+#[derive(Debug)]
+pub struct ConfirmationTracker {
+    pending: Mutex<HashMap<Hash, Instant>>,
+    seen_in_pool: Mutex<HashSet<Hash>>,
+    in_pool: RelaxedCounter,
+    committed: RelaxedCounter,
+    rejected: RelaxedCounter,
+    commit_latencies: Mutex<Vec<Duration>>,
+}
+
+impl ConfirmationTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            seen_in_pool: Mutex::new(HashSet::new()),
+            in_pool: RelaxedCounter::new(0),
+            committed: RelaxedCounter::new(0),
+            rejected: RelaxedCounter::new(0),
+            commit_latencies: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers a just-submitted transaction for confirmation tracking.
+    pub fn track(&self, hash: Hash) {
+        self.pending.lock().unwrap().insert(hash, Instant::now());
+    }
+
+    /// Number of transactions that never reached a terminal status.
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Mean time from submission to commitment, across committed transactions.
+    pub fn mean_commit_time(&self) -> Option<Duration> {
+        let latencies = self.commit_latencies.lock().unwrap();
+        let count = latencies.len() as u32;
+        (count > 0).then(|| latencies.iter().sum::<Duration>() / count)
+    }
+
+    /// Polls the status of every currently pending hash, up to
+    /// `POLL_CONCURRENCY` at a time, so a round takes about one round trip
+    /// per `POLL_CONCURRENCY` hashes instead of one round trip per hash.
+    async fn poll(&self, client: &Client, explorer_url: &str) {
+        let hashes: Vec<Hash> = self.pending.lock().unwrap().keys().copied().collect();
+        stream::iter(hashes)
+            .for_each_concurrent(POLL_CONCURRENCY, |hash| async move {
+                self.poll_one(client, explorer_url, hash).await;
+            })
+            .await;
+    }
+
+    /// Fetches and applies the explorer status of a single pending hash.
+    async fn poll_one(&self, client: &Client, explorer_url: &str, hash: Hash) {
+        let url = format!("{}?hash={}", explorer_url, hex::encode(hash.as_ref()));
+        let status = match client.get(&url).send().await {
+            Ok(response) => response.json::<ExplorerStatus>().await.ok(),
+            Err(_) => None,
+        };
+
+        match status {
+            Some(ExplorerStatus::InPool) => {
+                if self.seen_in_pool.lock().unwrap().insert(hash) {
+                    self.in_pool.inc();
+                }
+            }
+            Some(ExplorerStatus::Committed) => {
+                if let Some(submitted_at) = self.pending.lock().unwrap().remove(&hash) {
+                    self.committed.inc();
+                    self.commit_latencies
+                        .lock()
+                        .unwrap()
+                        .push(submitted_at.elapsed());
+                }
+            }
+            Some(ExplorerStatus::Rejected) => {
+                if self.pending.lock().unwrap().remove(&hash).is_some() {
+                    self.rejected.inc();
+                }
+            }
+            None => log::warn!("Failed to fetch status for tx {}", hex::encode(hash.as_ref())),
+        }
+    }
+
+    /// Polls `explorer_url` for pending transactions until `stop` is set,
+    /// then runs one last poll to catch any late confirmations.
+    pub async fn run_checker(&self, client: &Client, explorer_url: &str, stop: &AtomicBool) {
+        while !stop.load(Ordering::Relaxed) {
+            self.poll(client, explorer_url).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        self.poll(client, explorer_url).await;
+    }
+
+    /// Prints committed/rejected/still-pending totals and the mean
+    /// time-to-commit.
+    pub fn print_summary(&self) {
+        println!(
+            "Confirmations: {} committed, {} rejected, {} still pending, {} ever seen in-pool.",
+            self.committed.get(),
+            self.rejected.get(),
+            self.pending_count(),
+            self.in_pool.get()
+        );
+        if let Some(mean) = self.mean_commit_time() {
+            println!("Mean time-to-commit: {} ms.", mean.as_millis());
+        }
+    }
+}
+
+impl Default for ConfirmationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_track_and_pending_count() {
+    let tracker = ConfirmationTracker::new();
+    assert_eq!(tracker.pending_count(), 0);
+
+    tracker.track(Hash::zero());
+    assert_eq!(tracker.pending_count(), 1);
+}
+
+#[test]
+fn test_mean_commit_time_empty() {
+    let tracker = ConfirmationTracker::new();
+    assert!(tracker.mean_commit_time().is_none());
+}
+
+#[test]
+fn test_mean_commit_time_averages_latencies() {
+    let tracker = ConfirmationTracker::new();
+    tracker
+        .commit_latencies
+        .lock()
+        .unwrap()
+        .extend([Duration::from_millis(100), Duration::from_millis(300)]);
+
+    assert_eq!(tracker.mean_commit_time(), Some(Duration::from_millis(200)));
+}
+
+#[test]
+fn test_seen_in_pool_records_each_hash_once() {
+    let tracker = ConfirmationTracker::new();
+    let hash = Hash::zero();
+
+    assert!(tracker.seen_in_pool.lock().unwrap().insert(hash));
+    assert!(!tracker.seen_in_pool.lock().unwrap().insert(hash));
+}
@@ -0,0 +1,107 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime controls shared between sender threads and, in `--interactive`
+//! mode, the command prompt that adjusts them.
+
+use crate::rate_limiter::RateGovernor;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+/// Pause flag and rate governor shared across all sender threads.
+///
+/// Sender threads only read these; in interactive mode the REPL is the sole
+/// writer, so a plain `Mutex` swap is enough for the governor, which changes
+/// far less often than transactions are sent.
+#[derive(Debug)]
+pub struct Controls {
+    paused: AtomicBool,
+    shutdown: AtomicBool,
+    governor: Mutex<Option<Arc<RateGovernor>>>,
+}
+
+impl Controls {
+    /// Creates controls starting unpaused with the given initial governor.
+    pub fn new(governor: Option<Arc<RateGovernor>>) -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            shutdown: AtomicBool::new(false),
+            governor: Mutex::new(governor),
+        }
+    }
+
+    /// Whether sender threads should currently hold off sending.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Sets the pause flag.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// Whether transaction production has finished and sender threads
+    /// should stop waiting out a pause.
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// Signals that no more transactions will be queued, so a pause that is
+    /// never resumed must not keep sender threads waiting forever.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the currently configured rate governor, if any.
+    pub fn governor(&self) -> Option<Arc<RateGovernor>> {
+        self.governor.lock().unwrap().clone()
+    }
+
+    /// Replaces the rate governor.
+    pub fn set_governor(&self, governor: Option<Arc<RateGovernor>>) {
+        *self.governor.lock().unwrap() = governor;
+    }
+}
+
+#[test]
+fn test_starts_unpaused_and_running() {
+    let controls = Controls::new(None);
+    assert!(!controls.is_paused());
+    assert!(!controls.is_shutdown());
+    assert!(controls.governor().is_none());
+}
+
+#[test]
+fn test_set_paused_and_shutdown_are_independent() {
+    let controls = Controls::new(None);
+
+    controls.set_paused(true);
+    assert!(controls.is_paused());
+    assert!(!controls.is_shutdown());
+
+    controls.shutdown();
+    assert!(controls.is_paused());
+    assert!(controls.is_shutdown());
+}
+
+#[test]
+fn test_set_governor_replaces_existing() {
+    let controls = Controls::new(Some(Arc::new(RateGovernor::new(1.0, 1))));
+    assert!(controls.governor().is_some());
+
+    controls.set_governor(None);
+    assert!(controls.governor().is_none());
+}
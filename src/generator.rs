@@ -75,12 +75,28 @@ impl Iterator for CreateWalletGenerator {
     }
 }
 
+/// Wallet selection mode used when picking transfer participants.
+#[derive(Debug, Clone)]
+pub enum WalletDistribution {
+    /// Every wallet is equally likely to be picked.
+    Uniform,
+    /// Wallets are picked according to a Zipf distribution over wallet rank,
+    /// so a small fraction of "hot" wallets receives most of the transfers.
+    Zipf {
+        /// Skew parameter: higher values concentrate transfers on fewer wallets.
+        s: f64,
+    },
+}
+
 /// Config for `TransferGenerator`.
 pub struct TransferGeneratorConfig {
     pub service_id: u32,
     pub seed: u64,
     pub wallets_count: usize,
     pub wallets_seed: u64,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    pub distribution: WalletDistribution,
 }
 
 /// Generator for `Transfer` transactions.
@@ -90,24 +106,54 @@ pub struct TransferGenerator {
     seed: u64,
     wallets_count: usize,
     rand: XorShiftRng,
+    min_amount: u64,
+    max_amount: u64,
+    zipf_cdf: Option<Vec<f64>>,
 }
 
 impl TransferGenerator {
     pub fn new(conf: &TransferGeneratorConfig) -> Self {
         assert!(conf.wallets_count > 1);
+        assert!(conf.min_amount <= conf.max_amount);
 
         let mut buf = [0; 16];
         LittleEndian::write_u64(&mut buf, conf.seed);
         let rand = XorShiftRng::from_seed(buf);
 
+        let zipf_cdf = match conf.distribution {
+            WalletDistribution::Uniform => None,
+            WalletDistribution::Zipf { s } => Some(Self::zipf_cdf(conf.wallets_count, s)),
+        };
+
         Self {
             service_id: conf.service_id,
             seed: conf.wallets_seed,
             wallets_count: conf.wallets_count,
             rand,
+            min_amount: conf.min_amount,
+            max_amount: conf.max_amount,
+            zipf_cdf,
         }
     }
 
+    /// Builds the cumulative distribution function over wallet ranks
+    /// `1..=wallets_count`, weighted as `1 / rank^s`.
+    fn zipf_cdf(wallets_count: usize, s: f64) -> Vec<f64> {
+        let weights: Vec<f64> = (1..=wallets_count)
+            .map(|rank| 1.0 / (rank as f64).powf(s))
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut cumulative = 0.0;
+        weights
+            .into_iter()
+            .map(|weight| {
+                cumulative += weight / total;
+                cumulative
+            })
+            .collect()
+    }
+
     fn gen_keypair(&self, offset: u64) -> KeyPair {
         let mut buf = [0_u8; SEED_LENGTH];
         LittleEndian::write_u64(&mut buf, self.seed + offset);
@@ -115,7 +161,18 @@ impl TransferGenerator {
     }
 
     fn random_owner(&mut self) -> usize {
-        self.rand.gen_range(0..self.wallets_count)
+        match &self.zipf_cdf {
+            Some(cdf) => {
+                let target = self.rand.gen::<f64>();
+                cdf.partition_point(|&weight| weight < target)
+                    .min(self.wallets_count - 1)
+            }
+            None => self.rand.gen_range(0..self.wallets_count),
+        }
+    }
+
+    fn random_amount(&mut self) -> u64 {
+        self.rand.gen_range(self.min_amount..=self.max_amount)
     }
 }
 
@@ -134,7 +191,7 @@ impl Iterator for TransferGenerator {
             let keys = self.gen_keypair(from as u64);
             let to = CallerAddress::from_key(self.gen_keypair(to as u64).public_key());
             let seed = self.rand.gen();
-            let amount = self.rand.gen_range(1..10);
+            let amount = self.random_amount();
             let tx = keys.transfer(self.service_id, Transfer { to, amount, seed });
             return Some(tx);
         }
@@ -157,6 +214,9 @@ fn test_wallets_generator() {
         seed: 2000,
         wallets_seed,
         wallets_count,
+        min_amount: 1,
+        max_amount: 9,
+        distribution: WalletDistribution::Uniform,
     });
 
     gen.take(wallets_count)
@@ -179,6 +239,9 @@ fn test_wallets_generator2() {
         seed: 2000,
         wallets_seed,
         wallets_count,
+        min_amount: 1,
+        max_amount: 9,
+        distribution: WalletDistribution::Uniform,
     });
     assert_eq!(
         gen.map(|x| x.author())
@@ -206,6 +269,9 @@ fn test_transfer_generator() {
         seed,
         wallets_count,
         wallets_seed,
+        min_amount: 1,
+        max_amount: 9,
+        distribution: WalletDistribution::Uniform,
     });
 
     let wallets = wallet_gen
@@ -221,3 +287,43 @@ fn test_transfer_generator() {
     assert_eq!(wallets.len(), wallets_count);
     assert_eq!(txs.len(), txs_count);
 }
+
+#[test]
+fn test_transfer_generator_respects_amount_range() {
+    let mut gen = TransferGenerator::new(&TransferGeneratorConfig {
+        service_id: 1024,
+        seed: 1,
+        wallets_count: 10,
+        wallets_seed: 1,
+        min_amount: 42,
+        max_amount: 57,
+        distribution: WalletDistribution::Uniform,
+    });
+
+    for _ in 0..10_000 {
+        let amount = gen.random_amount();
+        assert!((42..=57).contains(&amount));
+    }
+}
+
+#[test]
+fn test_zipf_distribution_skews_towards_low_ranks() {
+    let wallets_count = 100;
+    let mut gen = TransferGenerator::new(&TransferGeneratorConfig {
+        service_id: 1024,
+        seed: 1,
+        wallets_count,
+        wallets_seed: 1,
+        min_amount: 1,
+        max_amount: 9,
+        distribution: WalletDistribution::Zipf { s: 2.0 },
+    });
+
+    let samples = 10_000;
+    let hits_on_wallet_zero = (0..samples).filter(|_| gen.random_owner() == 0).count();
+
+    // Under a uniform distribution wallet 0 would be picked ~1% of the time;
+    // a Zipf skew of s = 2 concentrates most picks on the lowest ranks.
+    let uniform_share = samples as f64 / wallets_count as f64;
+    assert!(hits_on_wallet_zero as f64 > uniform_share * 10.0);
+}
@@ -0,0 +1,151 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interactive command prompt for controlling a generation session at
+//! runtime, instead of running a fixed batch and exiting.
+
+use crate::{
+    controls::Controls,
+    rate_limiter::{is_valid_rate, RateGovernor},
+    TxMessage,
+};
+use atomic_counter::{AtomicCounter, RelaxedCounter};
+use exonum::{
+    merkledb::{BinaryValue, ObjectHash},
+    messages::{AnyTx, Verified},
+};
+use serde_json::json;
+use std::{
+    io::{self, BufRead},
+    sync::Arc,
+    time::Instant,
+};
+use tokio::sync::mpsc::Sender;
+
+/// Runs the interactive prompt until a `stop` command or EOF on stdin.
+///
+/// `start <count>` pulls the next `count` transactions off `tx_generator`
+/// and feeds them into `tx`; `pause`/`resume`/`rate` flip the shared
+/// `controls` that sender threads read on every send.
+///
+/// Runs on a blocking thread (see `tokio::task::spawn_blocking` in `main`),
+/// so it queues transactions with `blocking_send` rather than `.await`ing.
+pub fn run(
+    tx: &Sender<TxMessage>,
+    mut tx_generator: Box<dyn Iterator<Item = Verified<AnyTx>>>,
+    counter: &RelaxedCounter,
+    controls: &Controls,
+) {
+    println!("Interactive mode. Commands: start <count>, pause, resume, rate <rps>, stats, stop.");
+
+    let mut last_sample = (Instant::now(), counter.get());
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                log::error!("{}", e);
+                break;
+            }
+        };
+
+        let mut command = line.trim().split_whitespace();
+        match command.next() {
+            Some("start") => match command.next().and_then(|count| count.parse().ok()) {
+                Some(count) => {
+                    let queued = queue_transactions(tx, &mut tx_generator, count);
+                    println!("Queued {} transactions.", queued);
+                }
+                None => println!("Usage: start <count>"),
+            },
+            Some("pause") => {
+                controls.set_paused(true);
+                println!("Paused.");
+            }
+            Some("resume") => {
+                controls.set_paused(false);
+                println!("Resumed.");
+            }
+            Some("rate") => match command
+                .next()
+                .and_then(|rate| rate.parse::<f64>().ok())
+                .filter(|rate| is_valid_rate(*rate))
+            {
+                Some(rate) => {
+                    controls.set_governor(Some(Arc::new(RateGovernor::new(rate, 1))));
+                    println!("Target rate set to {} tx/s.", rate);
+                }
+                None => println!("Usage: rate <tx_per_sec>, with <tx_per_sec> a positive number"),
+            },
+            Some("stats") => {
+                let total = counter.get();
+                let now = Instant::now();
+                let (last_at, last_total) = last_sample;
+                let window_elapsed = now.duration_since(last_at).as_secs_f64();
+                let window_sent = total.saturating_sub(last_total);
+                let rps = if window_elapsed > 0.0 {
+                    window_sent as f64 / window_elapsed
+                } else {
+                    0.0
+                };
+                last_sample = (now, total);
+                println!(
+                    "Total sent: {}. Current RPS: {:.1}. Paused: {}.",
+                    total,
+                    rps,
+                    controls.is_paused()
+                );
+            }
+            Some("stop") => {
+                println!("Stopping.");
+                break;
+            }
+            Some(other) => println!("Unknown command: {}", other),
+            None => {}
+        }
+    }
+}
+
+fn queue_transactions(
+    tx: &Sender<TxMessage>,
+    tx_generator: &mut Box<dyn Iterator<Item = Verified<AnyTx>>>,
+    count: usize,
+) -> usize {
+    let mut queued = 0;
+    for t in tx_generator.take(count) {
+        let hash = t.object_hash();
+        let body = json!({ "tx_body": hex::encode(t.to_bytes())});
+        if let Err(e) = tx.blocking_send(TxMessage { body, hash }) {
+            log::error!("{}", e);
+            break;
+        }
+        queued += 1;
+    }
+    queued
+}
+
+#[test]
+fn test_queue_transactions_respects_count() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let mut tx_generator: Box<dyn Iterator<Item = Verified<AnyTx>>> =
+        Box::new(crate::generator::CreateWalletGenerator::new(1, 0));
+
+    let queued = queue_transactions(&tx, &mut tx_generator, 3);
+
+    assert_eq!(queued, 3);
+    assert!(rx.try_recv().is_ok());
+    assert!(rx.try_recv().is_ok());
+    assert!(rx.try_recv().is_ok());
+    assert!(rx.try_recv().is_err());
+}
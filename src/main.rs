@@ -24,26 +24,46 @@
 #![allow(clippy::module_name_repetitions)]
 
 use atomic_counter::{AtomicCounter, RelaxedCounter};
-use crossbeam::channel::{bounded, Sender, TryRecvError};
-use exonum::merkledb::BinaryValue;
-use exonum::messages::{AnyTx, Verified};
-use generator::{CreateWalletGenerator, TransferGenerator, TransferGeneratorConfig};
+use config::{Config, Endpoint};
+use confirmation::ConfirmationTracker;
+use controls::Controls;
+use exonum::merkledb::{BinaryValue, ObjectHash};
+use exonum::{
+    crypto::Hash,
+    messages::{AnyTx, Verified},
+};
+use futures::stream::{self, StreamExt};
+use generator::{
+    CreateWalletGenerator, TransferGenerator, TransferGeneratorConfig, WalletDistribution,
+};
 use logger::init_custom_logger;
-use reqwest::blocking::Client;
+use rate_limiter::{is_valid_rate, RateGovernor};
+use reqwest::Client;
 use serde_json::json;
 use std::{
     ops::Deref,
-    sync::{Arc, Mutex},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     thread,
     time::{Duration, SystemTime},
 };
 use structopt::StructOpt;
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
 
+mod config;
+mod confirmation;
+mod controls;
 mod generator;
+mod interactive;
 mod logger;
+mod rate_limiter;
 
 const TX_AMOUNT: usize = 10_000;
 const CHANNEL_SIZE: usize = 500_000;
+const DEFAULT_BURST_CAPACITY: u64 = 1;
 
 /// Generate hex encoded list of transactions.
 #[derive(Debug, StructOpt)]
@@ -61,17 +81,75 @@ struct Options {
     /// A transactions count.
     #[structopt(short = "a", long = "api", help = "Backend API")]
     api_hosts: Vec<String>,
+    /// Target aggregate rate.
+    #[structopt(
+        short = "r",
+        long = "rate",
+        help = "Target aggregate rate of sent transactions, in transactions per second",
+        validator = validate_rate
+    )]
+    rate: Option<f64>,
+    /// Config file listing API endpoints.
+    #[structopt(
+        long = "config",
+        help = "Path to a TOML config file listing API endpoints"
+    )]
+    config: Option<PathBuf>,
+    /// Confirmation mode.
+    #[structopt(
+        long = "confirm",
+        help = "Poll the explorer API for the status of submitted transactions"
+    )]
+    confirm: bool,
+    /// Interactive mode.
     #[structopt(
-        short = "t",
-        long = "timeout",
-        help = "A delay between sending transactions in microseconds"
+        long = "interactive",
+        help = "Drop into a command prompt controlling generation at runtime, instead of \
+                sending a fixed batch and exiting"
     )]
-    timeout: Option<u64>,
+    interactive: bool,
+    /// Per-host in-flight request concurrency.
+    #[structopt(
+        long = "in-flight",
+        default_value = "32",
+        help = "Maximum number of concurrent in-flight requests per API host"
+    )]
+    in_flight: usize,
     #[structopt(subcommand)]
     transaction: Transaction,
 }
 
+/// Rejects `--rate` values that `RateGovernor` cannot make sense of: the
+/// token-bucket math divides by the rate, so `RateGovernor::new` panics on
+/// zero, negative or non-finite values; checking here reports them as a
+/// usage error instead.
+fn validate_rate(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(rate) if is_valid_rate(rate) => Ok(()),
+        Ok(rate) => Err(format!("rate must be a positive number, got {}", rate)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 impl Options {
+    fn endpoints(&self) -> Vec<Endpoint> {
+        let mut endpoints: Vec<_> = self
+            .api_hosts
+            .iter()
+            .cloned()
+            .map(Endpoint::from_host)
+            .collect();
+
+        if let Some(config_path) = &self.config {
+            match Config::from_file(config_path) {
+                Ok(config) => endpoints.extend(config.endpoints),
+                Err(e) => log::error!("Failed to read config {:?}: {}", config_path, e),
+            }
+        }
+
+        endpoints
+    }
+
     fn create_tx_generator(&self) -> Box<dyn Iterator<Item = Verified<AnyTx>>> {
         match self.transaction {
             Transaction::CreateWallet => {
@@ -80,27 +158,45 @@ impl Options {
             Transaction::Transfer {
                 wallets_count,
                 wallets_seed,
+                min_amount,
+                max_amount,
+                zipf_skew,
             } => Box::new(TransferGenerator::new(&TransferGeneratorConfig {
                 service_id: self.service_id,
                 seed: self.seed,
                 wallets_count,
                 wallets_seed,
+                min_amount,
+                max_amount,
+                distribution: match zipf_skew {
+                    Some(s) => WalletDistribution::Zipf { s },
+                    None => WalletDistribution::Uniform,
+                },
             })),
         }
     }
 
-    fn generator(&self, tx: &Sender<serde_json::Value>) {
+    fn generator(&self, tx: &mpsc::Sender<TxMessage>) {
         let tx_generator = self.create_tx_generator();
 
         for t in tx_generator.take(self.count) {
-            let tx_body = json!({ "tx_body": hex::encode(t.to_bytes())});
-            if let Err(e) = tx.send(tx_body) {
+            let hash = t.object_hash();
+            let body = json!({ "tx_body": hex::encode(t.to_bytes())});
+            if let Err(e) = tx.blocking_send(TxMessage { body, hash }) {
                 log::error!("{}", e);
             }
         }
     }
 }
 
+/// A generated transaction paired with the hash used to track its
+/// confirmation status.
+#[derive(Debug, Clone)]
+pub(crate) struct TxMessage {
+    pub(crate) body: serde_json::Value,
+    pub(crate) hash: Hash,
+}
+
 #[derive(Debug, StructOpt)]
 enum Transaction {
     /// Generate create wallet transactions
@@ -113,16 +209,37 @@ enum Transaction {
         wallets_count: usize,
         #[structopt(long = "wallets-seed", help = "Wallets seed")]
         wallets_seed: u64,
+        /// A minimal transfer amount.
+        #[structopt(
+            long = "min-amount",
+            default_value = "1",
+            help = "Minimal transfer amount"
+        )]
+        min_amount: u64,
+        /// A maximal transfer amount.
+        #[structopt(
+            long = "max-amount",
+            default_value = "9",
+            help = "Maximal transfer amount"
+        )]
+        max_amount: u64,
+        /// Zipf skew parameter for a "hot wallet" distribution.
+        #[structopt(
+            long = "zipf-skew",
+            help = "Enables a Zipf-skewed wallet selection with the given skew parameter, instead of uniform selection"
+        )]
+        zipf_skew: Option<f64>,
     },
 }
 
-fn post_transaction(
+async fn post_transaction(
     client: &Client,
     url: &str,
-    tx: &serde_json::Value,
+    tx: &TxMessage,
     counter: &RelaxedCounter,
-    timeout: Option<u64>,
+    controls: &Controls,
     time: &Arc<Mutex<SystemTime>>,
+    tracker: &Option<Arc<ConfirmationTracker>>,
 ) {
     let tx_count = counter.inc();
     if tx_count % TX_AMOUNT == 0 && tx_count > 0 {
@@ -138,63 +255,149 @@ fn post_transaction(
         );
     }
 
-    if let Some(timeout) = timeout {
-        thread::sleep(Duration::from_micros(timeout));
+    while controls.is_paused() {
+        if controls.is_shutdown() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    if let Some(governor) = controls.governor() {
+        governor.acquire().await;
     }
 
-    log::info!("tx: {}", &tx);
-    let _ = client
-        .post(url)
-        .json(&tx)
-        .send()
-        .map_err(|err| log::error!("{}", err))
-        .and_then(|response| {
+    log::info!("tx: {}", &tx.body);
+    match client.post(url).json(&tx.body).send().await {
+        Ok(response) => {
             log::info!("Response: {:?}", response);
-            Ok(())
-        });
+            if let Some(tracker) = tracker {
+                tracker.track(tx.hash);
+            }
+        }
+        Err(e) => log::error!("{}", e),
+    }
 }
 
-fn main() {
+/// Drains the shared transaction channel for a single API host, keeping up to
+/// `in_flight` requests outstanding at once.
+async fn run_host(
+    rx: Arc<AsyncMutex<mpsc::Receiver<TxMessage>>>,
+    client: Client,
+    url: String,
+    counter: Arc<RelaxedCounter>,
+    controls: Arc<Controls>,
+    time: Arc<Mutex<SystemTime>>,
+    tracker: Option<Arc<ConfirmationTracker>>,
+    in_flight: usize,
+) {
+    stream::unfold(rx, |rx| async move {
+        let tx = rx.lock().await.recv().await;
+        tx.map(|tx| (tx, rx))
+    })
+    .for_each_concurrent(in_flight, |tx| {
+        let client = client.clone();
+        let url = url.clone();
+        let counter = counter.clone();
+        let controls = controls.clone();
+        let time = time.clone();
+        let tracker = tracker.clone();
+        async move {
+            post_transaction(&client, &url, &tx, &counter, &controls, &time, &tracker).await;
+        }
+    })
+    .await;
+}
+
+#[tokio::main]
+async fn main() {
     init_custom_logger().unwrap();
     let opts = Options::from_args();
     println!("Seed: {}. Transaction count: {}.", opts.seed, opts.count);
 
-    let (tx, rx) = bounded::<serde_json::Value>(CHANNEL_SIZE);
-    let hosts = opts.api_hosts.clone();
-    let timeout = opts.timeout;
-
-    let gen_handler = thread::spawn(move || {
-        opts.generator(&tx);
-    });
+    let (tx, rx) = mpsc::channel::<TxMessage>(CHANNEL_SIZE);
+    let rx = Arc::new(AsyncMutex::new(rx));
+    let endpoints = opts.endpoints();
+    let governor = opts
+        .rate
+        .map(|rate| Arc::new(RateGovernor::new(rate, DEFAULT_BURST_CAPACITY)));
+    let controls = Arc::new(Controls::new(governor));
+    let tracker = opts
+        .confirm
+        .then(|| Arc::new(ConfirmationTracker::new()));
+    let stop_checkers = Arc::new(AtomicBool::new(false));
+    let interactive = opts.interactive;
+    let in_flight = opts.in_flight;
 
     let time = Arc::new(Mutex::new(SystemTime::now()));
     let counter = Arc::new(RelaxedCounter::new(0));
-    let handlers = hosts.iter().map(|host| {
-        let time_ref = time.clone();
-        let counter_ref = counter.clone();
-        let tx_url = format!("http://{}/api/explorer/v1/transactions", host);
-        let client = Client::new();
-        let tx_channel = rx.clone();
-        thread::spawn(move || loop {
-            match tx_channel.try_recv() {
-                Ok(tx) => post_transaction(
-                    &client,
-                    &tx_url,
-                    &tx,
-                    counter_ref.deref(),
-                    timeout,
-                    &time_ref,
-                ),
-                Err(e) => match e {
-                    TryRecvError::Empty => log::warn!("No messages"),
-                    TryRecvError::Disconnected => break,
-                },
+    let mut checker_handlers = Vec::new();
+    let host_handlers: Vec<_> = endpoints
+        .into_iter()
+        .map(|endpoint| {
+            let time_ref = time.clone();
+            let counter_ref = counter.clone();
+            let controls_ref = controls.clone();
+            let tracker_ref = tracker.clone();
+            let tx_url = endpoint.transactions_url();
+            let client = Client::builder()
+                .danger_accept_invalid_certs(endpoint.no_cert_verification)
+                .build()
+                .unwrap();
+
+            if let Some(tracker) = &tracker {
+                let checker_tracker = tracker.clone();
+                let checker_client = client.clone();
+                let checker_url = tx_url.clone();
+                let checker_stop = stop_checkers.clone();
+                checker_handlers.push(tokio::spawn(async move {
+                    checker_tracker
+                        .run_checker(&checker_client, &checker_url, &checker_stop)
+                        .await;
+                }));
             }
+
+            let rx_ref = rx.clone();
+            tokio::spawn(run_host(
+                rx_ref,
+                client,
+                tx_url,
+                counter_ref,
+                controls_ref,
+                time_ref,
+                tracker_ref,
+                in_flight,
+            ))
         })
-    });
+        .collect();
+
+    if interactive {
+        let controls_ref = controls.clone();
+        let counter_ref = counter.clone();
+        let tx_generator = opts.create_tx_generator();
+        let repl = tokio::task::spawn_blocking(move || {
+            interactive::run(&tx, tx_generator, counter_ref.deref(), &controls_ref);
+        });
+        let _ = repl.await;
+    } else {
+        let gen_handler = thread::spawn(move || {
+            opts.generator(&tx);
+        });
+        let _ = gen_handler.join();
+    }
+
+    // No more transactions will be queued past this point, so a pause that
+    // is never resumed must not keep `run_host` waiting forever.
+    controls.shutdown();
 
-    let _ = gen_handler.join();
-    for handler in handlers {
-        let _ = handler.join();
+    for handler in host_handlers {
+        let _ = handler.await;
+    }
+
+    if let Some(tracker) = tracker {
+        stop_checkers.store(true, Ordering::Relaxed);
+        for checker in checker_handlers {
+            let _ = checker.await;
+        }
+        tracker.print_summary();
     }
 }